@@ -3,6 +3,16 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
+// default minimum amount of gas we want to keep in reserve before bailing out
+// of an offer-scanning loop, so that persisting the cursor + accumulator can
+// never itself run out of gas
+const DEFAULT_MIN_GAS_TO_SAVE_PROGRESS: u64 = 100_000_000;
+
+// settlement fee expressed in basis points (200 = 2%), capped so governance
+// cannot set a confiscatory rate
+const DEFAULT_FEE_BASIS_POINTS: u64 = 200;
+const MAX_FEE_BASIS_POINTS: u64 = 1000;
+
 #[derive(TypeAbi, NestedEncode, NestedDecode, TopEncode, TopDecode, PartialEq)]
 pub enum Status {
     Submitted,
@@ -10,6 +20,33 @@ pub enum Status {
     Deleted
 }
 
+// whether the creator escrowed an NFT and waits to be paid (Sell) or escrowed
+// funds up front to buy an NFT from the collection (Buy)
+#[derive(TypeAbi, NestedEncode, NestedDecode, TopEncode, TopDecode, PartialEq)]
+pub enum OfferKind {
+    Sell,
+    Buy
+}
+
+// outcome of a resumable scan: either we walked every id or we stopped early to
+// stay under the gas limit and saved our place for a follow-up call
+#[derive(TypeAbi, NestedEncode, NestedDecode, TopEncode, TopDecode, PartialEq)]
+pub enum OperationCompletionStatus {
+    Completed,
+    InterruptedBeforeOutOfGas
+}
+
+// persisted progress of an interrupted scan: the cursor to resume from (the next
+// id to inspect, or the next position in the list of ids being scanned) plus the
+// partial result gathered so far (a count or a vector of ids); getNbSubmittedFor
+// keeps its snapshot of the ids to scan in the vector and its result in the count
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct ScanProgress<M: ManagedTypeApi> {
+    pub last_processed_id: u64,
+    pub counter: u64,
+    pub accumulator: ManagedVec<M, u64>
+}
+
 #[derive(TypeAbi, TopEncode, TopDecode)]
 pub struct Offer<M: ManagedTypeApi> {
     pub id: u64,
@@ -18,6 +55,9 @@ pub struct Offer<M: ManagedTypeApi> {
     pub amount: BigUint<M>,
     pub token_id: TokenIdentifier<M>,
     pub nonce: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>, // token the nft_holder wants to be paid in (for a buy offer: the token the buyer escrowed)
+    pub kind: OfferKind, // sell offer (NFT escrowed) or buy offer (funds escrowed)
+    pub expiration: u64, // block timestamp after which the offer can no longer be settled
     pub status: Status
 }
 
@@ -27,24 +67,99 @@ pub trait Middleman {
    #[init]
    fn init(&self) -> SCResult<()> {
        self.offers_count().set_if_empty(&1u64);
+       self.min_gas_to_save_progress().set_if_empty(&DEFAULT_MIN_GAS_TO_SAVE_PROGRESS);
+       self.fee_basis_points().set_if_empty(&DEFAULT_FEE_BASIS_POINTS);
+       self.fee_treasury().set_if_empty(&self.blockchain().get_caller());
        Ok(())
    }
 
+   // returns false once the remaining gas drops below the configured threshold,
+   // signalling a scanning loop that it must stop and persist its progress
+   fn has_gas_for_another_step(&self) -> bool {
+       self.blockchain().get_gas_left() >= self.min_gas_to_save_progress().get()
+   }
+
    // only-owner
 
+   // admin & pause management
+
    #[only_owner]
-   #[endpoint(withdrawBalance)]
-   fn withdraw_balance(&self) {
+   #[endpoint(addAdmin)]
+   fn add_admin(&self, address: ManagedAddress) {
+       self.admins().insert(address);
+   }
+
+   #[only_owner]
+   #[endpoint(removeAdmin)]
+   fn remove_admin(&self, address: ManagedAddress) {
+       self.admins().swap_remove(&address);
+   }
+
+   #[endpoint(setPaused)]
+   fn set_paused(&self) {
+       self.require_caller_is_admin_or_owner();
+       self.paused_status().set(&true);
+   }
+
+   #[endpoint(unpause)]
+   fn unpause(&self) {
+       self.require_caller_is_admin_or_owner();
+       self.paused_status().set(&false);
+   }
+
+   #[view(isPaused)]
+   fn is_paused(&self) -> bool {
+       self.paused_status().get()
+   }
+
+   // trusted accounts are the contract owner plus any delegated admin
+   fn require_caller_is_admin_or_owner(&self) {
        let caller = self.blockchain().get_caller();
-       let sc_balance = self.blockchain().get_sc_balance(&TokenIdentifier::egld(), 0);
-       
-       self.send().direct_egld(
-           &caller,
-           &sc_balance,
-           &[]
+       let owner = self.blockchain().get_owner_address();
+       require!(
+           caller == owner || self.admins().contains(&caller),
+           "Caller is not an admin or the owner"
        );
    }
 
+   #[only_owner]
+   #[endpoint(setFeeBasisPoints)]
+   fn set_fee_basis_points(&self, bps: u64) {
+       require!(bps <= MAX_FEE_BASIS_POINTS, "fee too high");
+       self.fee_basis_points().set(&bps);
+   }
+
+   #[only_owner]
+   #[endpoint(setFeeTreasury)]
+   fn set_fee_treasury(&self, treasury: ManagedAddress) {
+       self.fee_treasury().set(&treasury);
+   }
+
+   // net amount owed to the seller after deducting the governance-set fee rate
+   fn net_after_fee(&self, amount: &BigUint) -> BigUint {
+       let bps = self.fee_basis_points().get();
+       amount * (10_000u64 - bps) / 10_000u64
+   }
+
+   // send EGLD or a single ESDT, switching on the token kind the way the rest of
+   // this contract's transfers do (the legacy `send()` API, not a tx builder)
+   fn send_payment(&self, to: &ManagedAddress, token: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
+       if token.is_egld() {
+           self.send().direct_egld(to, amount, &[]);
+       } else {
+           self.send().direct(to, &token.clone().unwrap_esdt(), 0, amount, &[]);
+       }
+   }
+
+   // route a settlement fee to the configured treasury rather than letting it
+   // accumulate in the contract
+   fn collect_fee(&self, token: &EgldOrEsdtTokenIdentifier, fee: &BigUint) {
+       if fee == &0 {
+           return;
+       }
+       self.send_payment(&self.fee_treasury().get(), token, fee);
+   }
+
    // endpoint 
 
    #[payable("*")]
@@ -54,10 +169,14 @@ pub trait Middleman {
        #[payment_token] token_id: TokenIdentifier, // the collection the nft_holder wants to sell
        #[payment_nonce] nonce: u64, // the nonce of the nft of the collection
        spender: ManagedAddress, // the address that will pay
+       payment_token: EgldOrEsdtTokenIdentifier, // the token the spender must pay in
        amount: BigUint, // amount to pay for the spender
+       expires_in: u64, // seconds from now after which the offer expires
     ) -> SCResult<u64> {
+        require!(!self.is_paused(), "contract is paused");
         let caller = self.blockchain().get_caller();
         require!(amount >= 0, "The amount specified is below zero");
+        require!(expires_in > 0, "Offer must not expire immediately");
 
         // creation of the offer and storage
         let id = self.offers_count().get();
@@ -72,6 +191,9 @@ pub trait Middleman {
             amount,
             token_id,
             nonce,
+            payment_token,
+            kind: OfferKind::Sell,
+            expiration: self.blockchain().get_block_timestamp() + expires_in,
             status: Status::Submitted
         };
 
@@ -86,24 +208,142 @@ pub trait Middleman {
         Ok(id)
     }
 
+    #[payable("*")]
+    #[endpoint(createBuyOffer)]
+    fn create_buy_offer(
+        &self,
+        #[payment_token] payment_token: EgldOrEsdtTokenIdentifier, // escrowed funds the buyer pays
+        #[payment_amount] payment_amount: BigUint, // amount escrowed up front
+        token_id: TokenIdentifier, // the collection the buyer wants to acquire
+        nonce: u64, // a specific item, or 0 for any item from the collection (floor offer)
+        expires_in: u64, // seconds from now after which the offer expires
+    ) -> SCResult<u64> {
+        require!(!self.is_paused(), "contract is paused");
+        let caller = self.blockchain().get_caller();
+        require!(payment_amount > 0, "The amount specified must be above zero");
+        require!(expires_in > 0, "Offer must not expire immediately");
+
+        // the attached payment is now escrowed by the contract until the offer
+        // is filled or deleted
+        let id = self.offers_count().get();
+        self.offers_from(&caller).update(|vec| vec.push(id));
+        self.offers_count().set(&id + 1);
+
+        let offer = Offer {
+            id,
+            spender: caller.clone(),
+            nft_holder: caller,
+            amount: payment_amount,
+            token_id,
+            nonce,
+            payment_token,
+            kind: OfferKind::Buy,
+            expiration: self.blockchain().get_block_timestamp() + expires_in,
+            status: Status::Submitted
+        };
+
+        self.offers_with_id(&id).set(offer);
+        Ok(id)
+    }
+
+    #[payable("*")]
+    #[endpoint(fillBuyOffer)]
+    fn fill_buy_offer(
+        &self,
+        #[payment_token] token_id: TokenIdentifier, // the NFT collection being delivered
+        #[payment_nonce] nonce: u64, // the nonce of the delivered NFT
+        #[payment_amount] amount: BigUint,
+        id: u64
+    ) -> SCResult<u64> {
+        require!(!self.is_paused(), "contract is paused");
+        let caller = self.blockchain().get_caller();
+        let mut offer = self.offers_with_id(&id).get();
+        require!(offer.kind == OfferKind::Buy, "This is not a buy offer");
+        require!(offer.status == Status::Submitted, "Offer deleted or completed");
+        require!(self.blockchain().get_block_timestamp() < offer.expiration, "offer expired");
+        require!(amount == 1u64, "Send exactly one NFT");
+        require!(token_id == offer.token_id, "Wrong collection");
+        // nonce == 0 on the offer means any item from the collection
+        if offer.nonce != 0 {
+            require!(nonce == offer.nonce, "Wrong nonce");
+        }
+
+        // fee taken from the escrowed funds at the governance-set rate
+        let real_amount = self.net_after_fee(&offer.amount);
+        let fee = &offer.amount - &real_amount;
+        self.collect_fee(&offer.payment_token, &fee);
+
+        // release the escrowed funds (minus fee) to the holder who filled it
+        self.send_payment(&caller, &offer.payment_token, &real_amount);
+
+        // deliver the NFT to the buyer who created the offer
+        self.send().direct(
+            &offer.nft_holder,
+            &token_id,
+            nonce,
+            &BigUint::from(1u64),
+            &[]
+        );
+
+        offer.status = Status::Completed;
+        self.offers_with_id(&id).set(offer);
+        Ok(id)
+    }
+
     #[endpoint(deleteOffer)]
     fn delete_offer(
         &self,
         id: u64 // id of the offer
     ) -> SCResult<u64> {
+        require!(!self.is_paused(), "contract is paused");
         let caller = self.blockchain().get_caller();
         let mut offer = self.offers_with_id(&id).get();
         require!(offer.nft_holder == caller, "You are not the creator of this offer");
         require!(offer.status == Status::Submitted, "Offer deleted or completed");
-        
-        self.send().direct(
-            &caller,
-            &offer.token_id,
-            offer.nonce,
-            &BigUint::from(1u64),
-            &[]
+
+        self.refund_escrow(&offer, &caller);
+
+        offer.status = Status::Deleted;
+        self.offers_with_id(&id).set(offer);
+        Ok(id)
+    }
+
+    // return the escrowed asset to `to`: the NFT for a sell offer, the funds for
+    // a buy offer
+    fn refund_escrow(&self, offer: &Offer<Self::Api>, to: &ManagedAddress) {
+        match offer.kind {
+            OfferKind::Sell => {
+                self.send().direct(
+                    to,
+                    &offer.token_id,
+                    offer.nonce,
+                    &BigUint::from(1u64),
+                    &[]
+                );
+            },
+            OfferKind::Buy => {
+                self.send_payment(to, &offer.payment_token, &offer.amount);
+            },
+        }
+    }
+
+    #[endpoint(reclaimExpired)]
+    fn reclaim_expired(
+        &self,
+        id: u64 // id of the offer to reclaim
+    ) -> SCResult<u64> {
+        require!(!self.is_paused(), "contract is paused");
+        let mut offer = self.offers_with_id(&id).get();
+        require!(offer.status == Status::Submitted, "Offer deleted or completed");
+        require!(
+            self.blockchain().get_block_timestamp() >= offer.expiration,
+            "offer not yet expired"
         );
-        
+
+        // permissionless: the escrow always goes back to the offer creator, so
+        // anyone (e.g. a keeper bot) can unlock a stuck asset
+        self.refund_escrow(&offer, &offer.nft_holder);
+
         offer.status = Status::Deleted;
         self.offers_with_id(&id).set(offer);
         Ok(id)
@@ -113,27 +353,27 @@ pub trait Middleman {
     #[endpoint(acceptOffer)]
     fn accept_offer(
         &self,
-        #[payment_token] token_id: TokenIdentifier,
-        #[payment_amount] egld_amount: BigUint,
+        #[payment_token] payment_token: EgldOrEsdtTokenIdentifier,
+        #[payment_amount] payment_amount: BigUint,
         id: u64
     ) -> SCResult<u64> {
+        require!(!self.is_paused(), "contract is paused");
         let caller = self.blockchain().get_caller();
         let mut offer = self.offers_with_id(&id).get();
+        require!(offer.kind == OfferKind::Sell, "Not a sell offer");
         require!(offer.spender == caller, "You are not the spender designated for this offer");
-        require!(token_id.is_egld(), "Only pay with egld");
+        require!(payment_token == offer.payment_token, "Wrong payment token");
         require!(offer.status == Status::Submitted, "Offer deleted or completed");
-        require!(egld_amount == offer.amount, "Incorrect egld amount");
+        require!(self.blockchain().get_block_timestamp() < offer.expiration, "offer expired");
+        require!(payment_amount == offer.amount, "Incorrect payment amount");
 
-        // fees of 2% 
-        let big_amount = egld_amount * BigUint::from(98u64);
-        let real_amount = big_amount / BigUint::from(100u64);
+        // fee taken in the offer's payment token at the governance-set rate
+        let real_amount = self.net_after_fee(&payment_amount);
+        let fee = &payment_amount - &real_amount;
+        self.collect_fee(&payment_token, &fee);
 
-        // send egld to previous holder + data for notifications
-        self.send().direct_egld(
-            &offer.nft_holder,
-            &real_amount,
-            ManagedBuffer::new_from_bytes("Someone just accepted your offer on https://www.middleman-nft.com 💸".as_bytes())
-        );
+        // send the requested payment (EGLD or ESDT) to the previous holder
+        self.send_payment(&offer.nft_holder, &payment_token, &real_amount);
 
         // send the nft to the caller
         self.send().direct(
@@ -151,65 +391,200 @@ pub trait Middleman {
         Ok(id)
     }
 
+    #[payable("*")]
+    #[endpoint(settleBatch)]
+    fn settle_batch(
+        &self,
+        #[payment_token] payment_token: EgldOrEsdtTokenIdentifier,
+        #[payment_amount] payment_amount: BigUint,
+        ids: ManagedVec<u64>
+    ) -> SCResult<BigUint> {
+        require!(!self.is_paused(), "contract is paused");
+        require!(!ids.is_empty(), "No offers to settle");
+        let caller = self.blockchain().get_caller();
+        let now = self.blockchain().get_block_timestamp();
+
+        // validate every offer before moving any asset, so a single bad offer
+        // reverts the whole batch; reject duplicate ids up front so the caller
+        // never overpays for an offer that can only be settled once
+        let mut seen: ManagedVec<u64> = ManagedVec::new();
+        let mut total: BigUint = BigUint::zero();
+        for id in &ids {
+            require!(!seen.contains(&id), "Duplicate offer id");
+            seen.push(id);
+            let offer = self.offers_with_id(&id).get();
+            require!(offer.kind == OfferKind::Sell, "Only sell offers can be batch settled");
+            require!(offer.spender == caller, "You are not the spender designated for this offer");
+            require!(offer.status == Status::Submitted, "Offer deleted or completed");
+            require!(now < offer.expiration, "offer expired");
+            require!(payment_token == offer.payment_token, "Wrong payment token");
+            total += &offer.amount;
+        }
+        require!(payment_amount == total, "Payment does not cover the batch total");
+
+        // all checks passed: distribute proceeds, deliver NFTs and accumulate the
+        // fees into one treasury transfer for uniform fee accounting
+        let mut total_fee = BigUint::zero();
+        for id in &ids {
+            let mut offer = self.offers_with_id(&id).get();
+            let real_amount = self.net_after_fee(&offer.amount);
+            total_fee += &offer.amount - &real_amount;
+
+            self.send_payment(&offer.nft_holder, &payment_token, &real_amount);
+
+            self.send().direct(
+                &caller,
+                &offer.token_id,
+                offer.nonce,
+                &BigUint::from(1u64),
+                &[]
+            );
+
+            offer.status = Status::Completed;
+            self.offers_with_id(&id).set(offer);
+        }
+        self.collect_fee(&payment_token, &total_fee);
+
+        Ok(total)
+    }
+
     // view
 
-    #[view(getNbSubmittedFor)]
-    fn get_nb_submitted_for(&self, caller: ManagedAddress) -> u64 {
-        let mut counter: u64 = 0;
-        let mut offers = self.offers_to(&caller).get();
-        let offers_from_address = self.offers_from(&caller).get();
-        offers.append_vec(offers_from_address);
-        for id in &offers {
-            match self.offers_with_id(&id).get().status {
-                Status::Submitted => counter += 1u64,
-                _ => (),
+    // The four scans below walk the offer id space and therefore grow with the
+    // number of offers. They are resumable endpoints rather than plain views:
+    // each invocation processes ids until it either finishes or runs low on gas,
+    // in which case it persists a cursor + accumulator and returns
+    // `InterruptedBeforeOutOfGas` so a follow-up call can pick up where it left
+    // off. On natural completion the progress storage is cleared.
+
+    #[endpoint(getNbSubmittedFor)]
+    fn get_nb_submitted_for(&self, caller: ManagedAddress) -> MultiValue2<OperationCompletionStatus, u64> {
+        let mapper = self.nb_submitted_progress(&caller);
+
+        // snapshot the id list into the accumulator on the first call; the cursor
+        // is a positional index, so recomputing the vector on resume would shift
+        // it if an offer was created for this address in the meantime
+        let mut progress = if mapper.is_empty() {
+            let mut offers = self.offers_to(&caller).get();
+            offers.append_vec(self.offers_from(&caller).get());
+            ScanProgress { last_processed_id: 0, counter: 0, accumulator: offers }
+        } else {
+            mapper.get()
+        };
+
+        let nb_offers = progress.accumulator.len() as u64;
+        let mut index = progress.last_processed_id; // cursor into the snapshot
+
+        while index < nb_offers {
+            if !self.has_gas_for_another_step() {
+                progress.last_processed_id = index;
+                let counter = progress.counter;
+                mapper.set(&progress);
+                return (OperationCompletionStatus::InterruptedBeforeOutOfGas, counter).into();
+            }
+            let id = progress.accumulator.get(index as usize);
+            if self.offers_with_id(&id).get().status == Status::Submitted {
+                progress.counter += 1u64;
             }
+            index += 1;
         }
-        counter
+
+        let counter = progress.counter;
+        mapper.clear();
+        (OperationCompletionStatus::Completed, counter).into()
     }
 
-    #[view(getOffersSubmittedTo)]
-    fn get_offers_submitted_to(&self, caller: ManagedAddress) -> ManagedVec<u64> {
-        let mut submitted_to_vec = ManagedVec::new();
+    #[endpoint(getOffersSubmittedTo)]
+    fn get_offers_submitted_to(&self, caller: ManagedAddress) -> MultiValue2<OperationCompletionStatus, ManagedVec<u64>> {
         let offers = self.offers_to(&caller).get();
-        for id in &offers {
-            match self.offers_with_id(&id).get().status {
-                Status::Submitted => submitted_to_vec.push(id),
-                _ => (),
-            }
-        }
-        submitted_to_vec
+        self.scan_submitted_ids(&self.submitted_to_progress(&caller), &offers)
     }
 
-    #[view(getOffersSubmittedFrom)]
-    fn get_offers_submitted_from(&self, caller: ManagedAddress) -> ManagedVec<u64> {
-        let mut submitted_from_vec = ManagedVec::new();
+    #[endpoint(getOffersSubmittedFrom)]
+    fn get_offers_submitted_from(&self, caller: ManagedAddress) -> MultiValue2<OperationCompletionStatus, ManagedVec<u64>> {
         let offers = self.offers_from(&caller).get();
-        for id in &offers {
-            match self.offers_with_id(&id).get().status {
-                Status::Submitted => submitted_from_vec.push(id),
-                _ => (),
+        self.scan_submitted_ids(&self.submitted_from_progress(&caller), &offers)
+    }
+
+    // shared body of the two "submitted ids for an address" scans: collect the
+    // ids of `offers` whose offer is still `Submitted`, resuming from the saved
+    // cursor and bailing out early when gas runs low
+    fn scan_submitted_ids(
+        &self,
+        progress_mapper: &SingleValueMapper<ScanProgress<Self::Api>>,
+        offers: &ManagedVec<u64>,
+    ) -> MultiValue2<OperationCompletionStatus, ManagedVec<u64>> {
+        let mut progress = self.load_scan_progress(progress_mapper, 0);
+        let mut index = progress.last_processed_id;
+        let nb_offers = offers.len() as u64;
+
+        while index < nb_offers {
+            if !self.has_gas_for_another_step() {
+                progress.last_processed_id = index;
+                let acc = progress.accumulator.clone();
+                progress_mapper.set(&progress);
+                return (OperationCompletionStatus::InterruptedBeforeOutOfGas, acc).into();
             }
+            let id = offers.get(index as usize);
+            if self.offers_with_id(&id).get().status == Status::Submitted {
+                progress.accumulator.push(id);
+            }
+            index += 1;
         }
-        submitted_from_vec
+
+        let acc = progress.accumulator;
+        progress_mapper.clear();
+        (OperationCompletionStatus::Completed, acc).into()
     }
 
-    #[view(getLastCompletedOffers)]
-    fn get_last_completed_offers(&self, nb_offers_to_display: u64) -> ManagedVec<u64> {
-        let mut last_completed_offers_vec = ManagedVec::new();
+    #[endpoint(getLastCompletedOffers)]
+    fn get_last_completed_offers(&self, nb_offers_to_display: u64) -> MultiValue2<OperationCompletionStatus, ManagedVec<u64>> {
         let nb_offers: u64 = self.offers_count().get();
+        // key the progress per caller and per requested size, so interleaving
+        // callers (or a resume issued with a different count) cannot read or
+        // corrupt each other's accumulator
+        let caller = self.blockchain().get_caller();
+        let progress_mapper = self.last_completed_progress(&caller, nb_offers_to_display);
+        // start at the highest id and walk downwards; the cursor is the next id
+        // to inspect (0 means "nothing left to scan")
+        let start = if nb_offers > 1 { nb_offers - 1 } else { 0 };
+        let mut progress = self.load_scan_progress(&progress_mapper, start);
+        let mut id = progress.last_processed_id;
+
+        while id >= 1 && (progress.accumulator.len() as u64) < nb_offers_to_display {
+            if !self.has_gas_for_another_step() {
+                progress.last_processed_id = id;
+                let acc = progress.accumulator.clone();
+                progress_mapper.set(&progress);
+                return (OperationCompletionStatus::InterruptedBeforeOutOfGas, acc).into();
+            }
+            if self.offers_with_id(&id).get().status == Status::Completed {
+                progress.accumulator.push(id);
+            }
+            id -= 1;
+        }
 
-        for id in (1..nb_offers).rev() {
-            if (last_completed_offers_vec.len() as u64) < nb_offers_to_display {
-                match self.offers_with_id(&id).get().status {
-                    Status::Completed => last_completed_offers_vec.push(id),
-                    _ => (),
-                }  
-            } else {
-                ()
+        let acc = progress.accumulator;
+        progress_mapper.clear();
+        (OperationCompletionStatus::Completed, acc).into()
+    }
+
+    // read back a previously interrupted scan, or start a fresh one positioned at
+    // `start` with an empty accumulator
+    fn load_scan_progress(
+        &self,
+        progress_mapper: &SingleValueMapper<ScanProgress<Self::Api>>,
+        start: u64,
+    ) -> ScanProgress<Self::Api> {
+        if progress_mapper.is_empty() {
+            ScanProgress {
+                last_processed_id: start,
+                counter: 0,
+                accumulator: ManagedVec::new(),
             }
+        } else {
+            progress_mapper.get()
         }
-        last_completed_offers_vec
     }
 
    // storage
@@ -218,6 +593,38 @@ pub trait Middleman {
    #[storage_mapper("offers_count")] // know an offer details based on its id
    fn offers_count(&self) -> SingleValueMapper<u64>;
 
+   #[view(getPausedStatus)]
+   #[storage_mapper("paused_status")] // when true, the endpoints that create, settle, delete or reclaim offers are frozen
+   fn paused_status(&self) -> SingleValueMapper<bool>;
+
+   #[view(getAdmins)]
+   #[storage_mapper("admins")] // delegated accounts trusted alongside the owner
+   fn admins(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+   #[view(getFeeBasisPoints)]
+   #[storage_mapper("fee_basis_points")] // settlement fee rate in basis points
+   fn fee_basis_points(&self) -> SingleValueMapper<u64>;
+
+   #[view(getFeeTreasury)]
+   #[storage_mapper("fee_treasury")] // address fees are routed to on each settlement
+   fn fee_treasury(&self) -> SingleValueMapper<ManagedAddress>;
+
+   #[view(getMinGasToSaveProgress)]
+   #[storage_mapper("min_gas_to_save_progress")] // gas reserve kept before bailing out of a scan
+   fn min_gas_to_save_progress(&self) -> SingleValueMapper<u64>;
+
+   #[storage_mapper("last_completed_progress")] // resumable state of getLastCompletedOffers, per caller and requested size
+   fn last_completed_progress(&self, caller: &ManagedAddress, nb_to_display: u64) -> SingleValueMapper<ScanProgress<Self::Api>>;
+
+   #[storage_mapper("nb_submitted_progress")] // resumable state of getNbSubmittedFor, per address
+   fn nb_submitted_progress(&self, address: &ManagedAddress) -> SingleValueMapper<ScanProgress<Self::Api>>;
+
+   #[storage_mapper("submitted_to_progress")] // resumable state of getOffersSubmittedTo, per address
+   fn submitted_to_progress(&self, address: &ManagedAddress) -> SingleValueMapper<ScanProgress<Self::Api>>;
+
+   #[storage_mapper("submitted_from_progress")] // resumable state of getOffersSubmittedFrom, per address
+   fn submitted_from_progress(&self, address: &ManagedAddress) -> SingleValueMapper<ScanProgress<Self::Api>>;
+
    #[view(getOffersWithId)]
    #[storage_mapper("offers_with_id")] // know an offer details based on its id
    fn offers_with_id(&self, id: &u64) -> SingleValueMapper<Offer<Self::Api>>;